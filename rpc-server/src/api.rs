@@ -0,0 +1,150 @@
+use std::{
+    collections::HashSet,
+    sync::{Arc, RwLock},
+};
+
+use anyhow::Result;
+use cid::Cid;
+use ipld_blockstore::BlockStore;
+use libp2p::PeerId;
+use store::Store;
+use tokio::sync::{broadcast, mpsc, oneshot};
+
+use crate::{
+    p2p_rpc::{BlockRpcRequest, BlockRpcResponse},
+    ws::SubscriptionEvent,
+};
+
+/// Capacity of the per-node event broadcast channel backing `ursa_subscribe`.
+/// Subscribers that fall more than this many events behind miss the oldest
+/// events (their next receive returns `RecvError::Lagged`) instead of the
+/// channel growing without bound.
+const EVENT_CHANNEL_CAPACITY: usize = 1024;
+
+/// Commands the RPC/HTTP surface sends to the node's network task.
+pub enum NetworkCommand {
+    /// Send a block-exchange request to `peer` and route the typed reply back
+    /// through `respond_to` once the network task's `RequestResponse`
+    /// behaviour resolves it.
+    BlockRpc {
+        peer: PeerId,
+        request: BlockRpcRequest,
+        respond_to: oneshot::Sender<Result<BlockRpcResponse>>,
+    },
+}
+
+/// Bridges the local HTTP/RPC surface to the node's block store and its
+/// network task. One instance is shared (via `Arc`) across every router.
+pub struct NodeNetworkInterface<S> {
+    pub store: Arc<Store<S>>,
+    pub network_send: mpsc::UnboundedSender<NetworkCommand>,
+    events: broadcast::Sender<SubscriptionEvent>,
+    known_peers: RwLock<HashSet<PeerId>>,
+}
+
+impl<S> NodeNetworkInterface<S>
+where
+    S: BlockStore + Sync + Send + 'static,
+{
+    pub fn new(store: Arc<Store<S>>, network_send: mpsc::UnboundedSender<NetworkCommand>) -> Self {
+        let (events, _) = broadcast::channel(EVENT_CHANNEL_CAPACITY);
+        Self {
+            store,
+            network_send,
+            events,
+            known_peers: RwLock::new(HashSet::new()),
+        }
+    }
+
+    /// Subscribes to node activity (new blocks, peer connect/disconnect) for
+    /// `ursa_subscribe`. A lagging subscriber gets `RecvError::Lagged` rather
+    /// than stalling the broadcaster.
+    pub fn subscribe_events(&self) -> broadcast::Receiver<SubscriptionEvent> {
+        self.events.subscribe()
+    }
+
+    /// Called whenever a block lands in the local store, whether from a local
+    /// HTTP `put` or an inbound p2p `PutBlock`, fanning the event out to every
+    /// `ursa_subscribe`r.
+    pub fn notify_new_block(&self, cid: Cid) {
+        // No subscribers is the common case, not an error.
+        let _ = self.events.send(SubscriptionEvent::NewBlock {
+            cid: cid.to_string(),
+        });
+    }
+
+    // TODO(follow-up, tracked against the libp2p block-exchange request):
+    // neither `notify_peer_connected` nor `notify_peer_disconnected` is
+    // called anywhere in this repository checkout, so `known_peers()` is
+    // always empty and `fan_out_get`'s peer loop never runs today. Both need
+    // to be wired from `UrsaService`'s swarm event loop (peer
+    // connection/disconnection events), which lives in the `network` crate
+    // and is not part of this checkout.
+    /// Called by the network task when a peer connects. Tracked peers are
+    /// the fan-out set for `GetBlock` on a local cache miss.
+    pub fn notify_peer_connected(&self, peer: PeerId) {
+        self.known_peers
+            .write()
+            .expect("known_peers lock poisoned")
+            .insert(peer);
+        let _ = self.events.send(SubscriptionEvent::PeerConnected {
+            peer_id: peer.to_string(),
+        });
+    }
+
+    /// Called by the network task when a peer disconnects.
+    pub fn notify_peer_disconnected(&self, peer: PeerId) {
+        self.known_peers
+            .write()
+            .expect("known_peers lock poisoned")
+            .remove(&peer);
+        let _ = self.events.send(SubscriptionEvent::PeerDisconnected {
+            peer_id: peer.to_string(),
+        });
+    }
+
+    /// Peers currently known to be connected, in no particular order.
+    pub fn known_peers(&self) -> Vec<PeerId> {
+        self.known_peers
+            .read()
+            .expect("known_peers lock poisoned")
+            .iter()
+            .copied()
+            .collect()
+    }
+
+    /// Reads a block straight out of the local store. Backs both inbound p2p
+    /// `GetBlock` requests and the local HTTP `get` route.
+    pub fn get_local_block(&self, cid: &Cid) -> Result<Option<Vec<u8>>> {
+        self.store.get_bytes(cid)
+    }
+
+    /// Writes a block straight into the local store. Backs both inbound p2p
+    /// `PutBlock` requests and the local HTTP `put` route.
+    pub fn put_local_block(&self, cid: &Cid, data: &[u8]) -> Result<()> {
+        self.store.put_keyed(cid, data)
+    }
+
+    /// Sends a block-exchange request to `peer` through the network task and
+    /// awaits its typed reply. Used by the HTTP `get` route to fan out to
+    /// known peers on a local cache miss.
+    pub async fn send_block_rpc(
+        &self,
+        peer: PeerId,
+        request: BlockRpcRequest,
+    ) -> Result<BlockRpcResponse> {
+        let (respond_to, response) = oneshot::channel();
+
+        self.network_send
+            .send(NetworkCommand::BlockRpc {
+                peer,
+                request,
+                respond_to,
+            })
+            .map_err(|_| anyhow::anyhow!("network task has shut down"))?;
+
+        response
+            .await
+            .map_err(|_| anyhow::anyhow!("network task dropped the block rpc response"))?
+    }
+}
@@ -0,0 +1,183 @@
+use std::{fs, path::Path, sync::Arc};
+
+use anyhow::{Context, Result};
+use axum::{
+    body::Body,
+    extract::Extension,
+    http::{Request, StatusCode},
+    middleware::Next,
+    response::Response,
+};
+use rand::{distributions::Alphanumeric, Rng};
+use subtle::ConstantTimeEq;
+
+/// Shared secret gating the RPC surface. `None` disables auth entirely.
+pub type AuthToken = Arc<Option<String>>;
+
+/// Reads the bearer token from `path`, generating and persisting a fresh random
+/// token on first boot if the file doesn't exist yet.
+pub fn load_or_generate_token(path: &Path) -> Result<String> {
+    if let Ok(existing) = fs::read_to_string(path) {
+        let token = existing.trim().to_string();
+        if !token.is_empty() {
+            return Ok(token);
+        }
+    }
+
+    let token: String = rand::thread_rng()
+        .sample_iter(&Alphanumeric)
+        .take(48)
+        .map(char::from)
+        .collect();
+
+    write_token_file(path, &token)
+        .with_context(|| format!("failed to persist generated auth token at {path:?}"))?;
+
+    Ok(token)
+}
+
+/// Writes `token` to `path` with owner-only permissions (`0600` on unix) so
+/// the generated secret gating the whole RPC surface isn't left
+/// world-readable on the default umask.
+#[cfg(unix)]
+fn write_token_file(path: &Path, token: &str) -> std::io::Result<()> {
+    use std::{
+        io::Write,
+        os::unix::fs::{OpenOptionsExt, PermissionsExt},
+    };
+
+    let mut file = fs::OpenOptions::new()
+        .write(true)
+        .create(true)
+        .truncate(true)
+        .mode(0o600)
+        .open(path)?;
+    // `mode` only applies when the file is created; tighten an existing one too.
+    file.set_permissions(fs::Permissions::from_mode(0o600))?;
+
+    file.write_all(token.as_bytes())
+}
+
+#[cfg(not(unix))]
+fn write_token_file(path: &Path, token: &str) -> std::io::Result<()> {
+    fs::write(path, token)
+}
+
+/// Tower middleware that rejects requests lacking a matching bearer token,
+/// either via the `Authorization: Bearer <token>` header or a `?token=` query
+/// parameter. A no-op when no token is configured.
+pub async fn require_bearer_token(
+    Extension(expected): Extension<AuthToken>,
+    request: Request<Body>,
+    next: Next<Body>,
+) -> Response {
+    let Some(expected) = expected.as_ref() else {
+        return next.run(request).await;
+    };
+
+    let provided = bearer_header(&request).or_else(|| query_token(&request));
+
+    match provided {
+        Some(provided) if tokens_match(expected, &provided) => next.run(request).await,
+        _ => Response::builder()
+            .status(StatusCode::UNAUTHORIZED)
+            .body(Body::empty())
+            .expect("static response is valid"),
+    }
+}
+
+fn bearer_header(request: &Request<Body>) -> Option<String> {
+    request
+        .headers()
+        .get(axum::http::header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "))
+        .map(str::to_string)
+}
+
+fn query_token(request: &Request<Body>) -> Option<String> {
+    let query = request.uri().query()?;
+    url::form_urlencoded::parse(query.as_bytes())
+        .find(|(key, _)| key == "token")
+        .map(|(_, value)| value.into_owned())
+}
+
+fn tokens_match(expected: &str, provided: &str) -> bool {
+    expected.as_bytes().ct_eq(provided.as_bytes()).into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::http::header::AUTHORIZATION;
+
+    #[test]
+    fn tokens_match_rejects_mismatch_and_accepts_equal() {
+        assert!(tokens_match("secret", "secret"));
+        assert!(!tokens_match("secret", "wrong"));
+        assert!(!tokens_match("secret", "secre"));
+    }
+
+    #[test]
+    fn bearer_header_extracts_token_from_authorization_header() {
+        let request = Request::builder()
+            .header(AUTHORIZATION, "Bearer my-token")
+            .body(Body::empty())
+            .unwrap();
+
+        assert_eq!(bearer_header(&request), Some("my-token".to_string()));
+    }
+
+    #[test]
+    fn bearer_header_ignores_non_bearer_schemes() {
+        let request = Request::builder()
+            .header(AUTHORIZATION, "Basic dXNlcjpwYXNz")
+            .body(Body::empty())
+            .unwrap();
+
+        assert_eq!(bearer_header(&request), None);
+    }
+
+    #[test]
+    fn query_token_extracts_token_param() {
+        let request = Request::builder()
+            .uri("/rpc?token=my-token&other=1")
+            .body(Body::empty())
+            .unwrap();
+
+        assert_eq!(query_token(&request), Some("my-token".to_string()));
+    }
+
+    #[test]
+    fn query_token_none_without_token_param() {
+        let request = Request::builder()
+            .uri("/rpc?other=1")
+            .body(Body::empty())
+            .unwrap();
+
+        assert_eq!(query_token(&request), None);
+    }
+
+    #[test]
+    fn load_or_generate_token_persists_with_owner_only_permissions() {
+        let dir = std::env::temp_dir().join(format!(
+            "ursa-auth-token-test-{}",
+            std::process::id()
+        ));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("token");
+
+        let generated = load_or_generate_token(&path).unwrap();
+        let reloaded = load_or_generate_token(&path).unwrap();
+        assert_eq!(generated, reloaded, "token must persist across boots");
+
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = fs::metadata(&path).unwrap().permissions().mode() & 0o777;
+            assert_eq!(mode, 0o600);
+        }
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,149 @@
+use std::net::{IpAddr, SocketAddr};
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+
+/// Default bind address for the standalone RPC listener. Kept private to the
+/// node unless an operator opts into a different `rpc_addr`.
+const DEFAULT_RPC_ADDR: &str = "127.0.0.1";
+
+/// Default bind address for the standalone HTTP listener.
+const DEFAULT_HTTP_ADDR: &str = "0.0.0.0";
+
+/// Configuration for the node's RPC and HTTP server.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ServerConfig {
+    /// Configured bind address. Not used by the server: when
+    /// `http_port`/`rpc_port` aren't both set, the multiplexed HTTP+RPC
+    /// router always binds `0.0.0.0` on `port`.
+    pub addr: String,
+    /// Bind port used when `http_port`/`rpc_port` aren't both set.
+    pub port: u16,
+    /// Path to a PEM-encoded TLS certificate chain. When set together with
+    /// `tls_key_path`, the server's listeners (the multiplexed one, or both
+    /// split HTTP and RPC listeners) terminate TLS instead of serving
+    /// plaintext.
+    #[serde(default)]
+    pub tls_cert_path: Option<String>,
+    /// Path to the PEM-encoded (PKCS#8 or RSA) private key matching `tls_cert_path`.
+    #[serde(default)]
+    pub tls_key_path: Option<String>,
+    /// Path to a PEM-encoded CA bundle used to verify client certificates. When
+    /// set, the listener requires and verifies a client certificate for every
+    /// connection (mutual TLS).
+    #[serde(default)]
+    pub tls_client_ca_path: Option<String>,
+    /// Path to a file holding the bearer token that gates the RPC surface. The
+    /// token is generated and persisted here on first boot if the file is
+    /// missing or empty. When unset, the RPC router is unauthenticated.
+    #[serde(default)]
+    pub auth_token_path: Option<String>,
+    /// Bind address for the public HTTP gateway when split from RPC. Defaults
+    /// to `0.0.0.0`. Only takes effect when `http_port` is also set.
+    #[serde(default)]
+    pub http_addr: Option<String>,
+    /// Bind port for the public HTTP gateway. Set together with `rpc_port` to
+    /// run HTTP and RPC on independent listeners instead of the multiplexed
+    /// listener on `port`.
+    #[serde(default)]
+    pub http_port: Option<u16>,
+    /// Bind address for the administrative RPC listener when split from HTTP.
+    /// Defaults to `127.0.0.1` so RPC isn't exposed publicly by accident.
+    #[serde(default)]
+    pub rpc_addr: Option<String>,
+    /// Bind port for the administrative RPC listener. Set together with
+    /// `http_port` to run HTTP and RPC on independent listeners.
+    #[serde(default)]
+    pub rpc_port: Option<u16>,
+}
+
+impl Default for ServerConfig {
+    fn default() -> Self {
+        Self {
+            addr: "0.0.0.0".to_string(),
+            port: 4069,
+            tls_cert_path: None,
+            tls_key_path: None,
+            tls_client_ca_path: None,
+            auth_token_path: None,
+            http_addr: None,
+            http_port: None,
+            rpc_addr: None,
+            rpc_port: None,
+        }
+    }
+}
+
+impl ServerConfig {
+    /// When both `http_port` and `rpc_port` are set, returns the two
+    /// independent listener addresses to bind instead of the multiplexed
+    /// listener on `port`. A malformed `http_addr`/`rpc_addr` is a
+    /// configuration error, not a silent fallback to the merged listener —
+    /// an operator who typo'd `rpc_addr` to keep RPC off the public
+    /// interface must not end up with RPC back on the multiplexed address.
+    pub fn split_listen_addrs(&self) -> Result<Option<(SocketAddr, SocketAddr)>> {
+        let (Some(http_port), Some(rpc_port)) = (self.http_port, self.rpc_port) else {
+            return Ok(None);
+        };
+
+        let http_addr_str = self.http_addr.as_deref().unwrap_or(DEFAULT_HTTP_ADDR);
+        let http_addr: IpAddr = http_addr_str
+            .parse()
+            .with_context(|| format!("invalid http_addr {http_addr_str:?}"))?;
+
+        let rpc_addr_str = self.rpc_addr.as_deref().unwrap_or(DEFAULT_RPC_ADDR);
+        let rpc_addr: IpAddr = rpc_addr_str
+            .parse()
+            .with_context(|| format!("invalid rpc_addr {rpc_addr_str:?}"))?;
+
+        Ok(Some((
+            SocketAddr::new(http_addr, http_port),
+            SocketAddr::new(rpc_addr, rpc_port),
+        )))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn split_listen_addrs_none_when_neither_port_set() {
+        let config = ServerConfig::default();
+        assert!(config.split_listen_addrs().unwrap().is_none());
+    }
+
+    #[test]
+    fn split_listen_addrs_none_when_only_one_port_set() {
+        let config = ServerConfig {
+            http_port: Some(8080),
+            ..Default::default()
+        };
+        assert!(config.split_listen_addrs().unwrap().is_none());
+    }
+
+    #[test]
+    fn split_listen_addrs_applies_defaults() {
+        let config = ServerConfig {
+            http_port: Some(8080),
+            rpc_port: Some(8081),
+            ..Default::default()
+        };
+
+        let (http, rpc) = config.split_listen_addrs().unwrap().unwrap();
+        assert_eq!(http, "0.0.0.0:8080".parse().unwrap());
+        assert_eq!(rpc, "127.0.0.1:8081".parse().unwrap());
+    }
+
+    #[test]
+    fn split_listen_addrs_errors_on_unparseable_addr() {
+        let config = ServerConfig {
+            http_port: Some(8080),
+            rpc_port: Some(8081),
+            rpc_addr: Some("localhost".to_string()),
+            ..Default::default()
+        };
+
+        assert!(config.split_listen_addrs().is_err());
+    }
+}
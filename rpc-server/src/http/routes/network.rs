@@ -0,0 +1,101 @@
+use std::sync::Arc;
+
+use axum::{
+    body::Bytes,
+    extract::{Extension, Path},
+    http::StatusCode,
+    response::{IntoResponse, Response},
+    routing::get,
+    Router,
+};
+use cid::Cid;
+use ipld_blockstore::BlockStore;
+use tracing::warn;
+
+use crate::{api::NodeNetworkInterface, p2p_rpc};
+
+/// Public HTTP surface over the node's block store: `GET`/`PUT` a block by
+/// CID. A `GET` that misses the local store falls back to the p2p
+/// block-exchange RPC against known peers before answering 404; a `PUT`
+/// writes to the local store only.
+pub fn init<S>() -> Router
+where
+    S: BlockStore + Sync + Send + 'static,
+{
+    Router::new().route("/blocks/:cid", get(get_block::<S>).put(put_block::<S>))
+}
+
+async fn get_block<S>(
+    Path(cid): Path<String>,
+    Extension(interface): Extension<Arc<NodeNetworkInterface<S>>>,
+) -> Response
+where
+    S: BlockStore + Sync + Send + 'static,
+{
+    let cid = match cid.parse::<Cid>() {
+        Ok(cid) => cid,
+        Err(_) => return (StatusCode::BAD_REQUEST, "invalid cid").into_response(),
+    };
+
+    match interface.get_local_block(&cid) {
+        Ok(Some(data)) => (StatusCode::OK, data).into_response(),
+        Ok(None) => match fan_out_get(&interface, cid).await {
+            Some(data) => (StatusCode::OK, data).into_response(),
+            None => (StatusCode::NOT_FOUND, "block not found").into_response(),
+        },
+        Err(err) => {
+            warn!("local store lookup for {cid} failed: {err}");
+            (StatusCode::INTERNAL_SERVER_ERROR, "store error").into_response()
+        }
+    }
+}
+
+/// On a local cache miss, asks every known peer in turn for the block over
+/// the p2p `GetBlock` RPC before giving up.
+async fn fan_out_get<S>(interface: &NodeNetworkInterface<S>, cid: Cid) -> Option<Vec<u8>>
+where
+    S: BlockStore + Sync + Send + 'static,
+{
+    for peer in interface.known_peers() {
+        let result = p2p_rpc::rpc_get_block(peer, cid, |peer, request| async move {
+            interface.send_block_rpc(peer, request).await
+        })
+        .await;
+
+        match result {
+            Ok(Some(data)) => return Some(data),
+            Ok(None) => continue,
+            Err(err) => {
+                warn!("block rpc to {peer} for {cid} failed: {err}");
+                continue;
+            }
+        }
+    }
+
+    None
+}
+
+async fn put_block<S>(
+    Path(cid): Path<String>,
+    Extension(interface): Extension<Arc<NodeNetworkInterface<S>>>,
+    body: Bytes,
+) -> Response
+where
+    S: BlockStore + Sync + Send + 'static,
+{
+    let cid = match cid.parse::<Cid>() {
+        Ok(cid) => cid,
+        Err(_) => return (StatusCode::BAD_REQUEST, "invalid cid").into_response(),
+    };
+
+    match interface.put_local_block(&cid, &body) {
+        Ok(()) => {
+            interface.notify_new_block(cid);
+            StatusCode::NO_CONTENT.into_response()
+        }
+        Err(err) => {
+            warn!("local store write for {cid} failed: {err}");
+            (StatusCode::INTERNAL_SERVER_ERROR, "store error").into_response()
+        }
+    }
+}
@@ -0,0 +1,405 @@
+//! Node-to-node block exchange carried over a dedicated libp2p
+//! request-response protocol, so one ursa node can fetch or push a block
+//! directly from/to another node instead of only serving its own local
+//! `BlockStore` over HTTP. [`new_behaviour`] builds the swarm behaviour and
+//! [`handle_inbound_request`] answers it out of the local store; the HTTP
+//! `get` route in `http::routes::network` falls back to [`rpc_get_block`]
+//! on a local cache miss. [`rpc_put_block`] has no caller in this crate.
+//!
+//! TODO(follow-up, tracked against this request): `new_behaviour()` is not
+//! registered on any swarm, `handle_inbound_request` is not called from any
+//! swarm event loop, and `NodeNetworkInterface::send_block_rpc`'s
+//! `NetworkCommand::BlockRpc` has no consumer — so `rpc_get_block`/
+//! `rpc_put_block` will time out against a real peer today. All three need
+//! `UrsaService`'s swarm (the `network` crate), which is not part of this
+//! repository checkout. `handle_inbound_request`'s store-facing logic is
+//! covered directly by this module's tests in the meantime.
+
+use std::{io, time::Duration};
+
+use async_trait::async_trait;
+use cid::Cid;
+use futures::{AsyncRead, AsyncWrite, AsyncReadExt, AsyncWriteExt};
+use libp2p::request_response::{ProtocolName, RequestResponseCodec};
+use serde::{Deserialize, Serialize};
+
+/// Protocol name advertised for the block exchange request-response behaviour.
+#[derive(Debug, Clone, Default)]
+pub struct BlockExchangeProtocol;
+
+impl ProtocolName for BlockExchangeProtocol {
+    fn protocol_name(&self) -> &[u8] {
+        b"/ursa/block-exchange/1.0.0"
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum BlockRpcRequest {
+    GetBlock { cid: Cid },
+    PutBlock { cid: Cid, data: Vec<u8> },
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum BlockRpcResponse {
+    BlockData(Vec<u8>),
+    NotFound,
+    Ack,
+    Err(String),
+}
+
+/// How long a node waits for a peer to answer a block RPC before giving up.
+pub const BLOCK_RPC_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Number of times a block RPC is retried against the same peer before the
+/// caller is told to fall back (e.g. try another peer, or fail the request).
+pub const BLOCK_RPC_RETRIES: usize = 2;
+
+/// Maximum accepted frame size for a single request/response payload.
+const MAX_FRAME_LEN: usize = 64 * 1024 * 1024;
+
+#[derive(Debug, Clone, Default)]
+pub struct BlockExchangeCodec;
+
+#[async_trait]
+impl RequestResponseCodec for BlockExchangeCodec {
+    type Protocol = BlockExchangeProtocol;
+    type Request = BlockRpcRequest;
+    type Response = BlockRpcResponse;
+
+    async fn read_request<T>(
+        &mut self,
+        _: &BlockExchangeProtocol,
+        io: &mut T,
+    ) -> io::Result<Self::Request>
+    where
+        T: AsyncRead + Unpin + Send,
+    {
+        read_framed(io).await
+    }
+
+    async fn read_response<T>(
+        &mut self,
+        _: &BlockExchangeProtocol,
+        io: &mut T,
+    ) -> io::Result<Self::Response>
+    where
+        T: AsyncRead + Unpin + Send,
+    {
+        read_framed(io).await
+    }
+
+    async fn write_request<T>(
+        &mut self,
+        _: &BlockExchangeProtocol,
+        io: &mut T,
+        req: Self::Request,
+    ) -> io::Result<()>
+    where
+        T: AsyncWrite + Unpin + Send,
+    {
+        write_framed(io, &req).await
+    }
+
+    async fn write_response<T>(
+        &mut self,
+        _: &BlockExchangeProtocol,
+        io: &mut T,
+        res: Self::Response,
+    ) -> io::Result<()>
+    where
+        T: AsyncWrite + Unpin + Send,
+    {
+        write_framed(io, &res).await
+    }
+}
+
+async fn read_framed<T, M>(io: &mut T) -> io::Result<M>
+where
+    T: AsyncRead + Unpin + Send,
+    M: for<'de> Deserialize<'de>,
+{
+    let mut len_buf = [0u8; 4];
+    io.read_exact(&mut len_buf).await?;
+    let len = u32::from_be_bytes(len_buf) as usize;
+
+    if len > MAX_FRAME_LEN {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("block rpc frame of {len} bytes exceeds the {MAX_FRAME_LEN} byte limit"),
+        ));
+    }
+
+    let mut buf = vec![0u8; len];
+    io.read_exact(&mut buf).await?;
+
+    bincode::deserialize(&buf).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+}
+
+async fn write_framed<T, M>(io: &mut T, message: &M) -> io::Result<()>
+where
+    T: AsyncWrite + Unpin + Send,
+    M: Serialize,
+{
+    let buf = bincode::serialize(message).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+    io.write_all(&(buf.len() as u32).to_be_bytes()).await?;
+    io.write_all(&buf).await?;
+    io.flush().await
+}
+
+/// Sends `request` to `peer` through `dispatch` (the caller's hook into
+/// `UrsaService`'s request-response behaviour), retrying up to
+/// [`BLOCK_RPC_RETRIES`] times and bailing out once [`BLOCK_RPC_TIMEOUT`]
+/// elapses on the final attempt.
+async fn send_with_retry<F, Fut>(
+    peer: libp2p::PeerId,
+    request: BlockRpcRequest,
+    mut dispatch: F,
+) -> anyhow::Result<BlockRpcResponse>
+where
+    F: FnMut(libp2p::PeerId, BlockRpcRequest) -> Fut,
+    Fut: std::future::Future<Output = anyhow::Result<BlockRpcResponse>>,
+{
+    let mut last_err = None;
+
+    for _ in 0..=BLOCK_RPC_RETRIES {
+        match tokio::time::timeout(BLOCK_RPC_TIMEOUT, dispatch(peer, request.clone())).await {
+            Ok(Ok(response)) => return Ok(response),
+            Ok(Err(err)) => last_err = Some(err),
+            Err(_) => {
+                last_err = Some(anyhow::anyhow!(
+                    "timed out waiting for {peer} to answer a block rpc"
+                ))
+            }
+        }
+    }
+
+    Err(last_err.unwrap_or_else(|| anyhow::anyhow!("block rpc to {peer} failed")))
+}
+
+/// Fetches a block from `peer`'s store, returning `Ok(None)` if the peer
+/// doesn't have it. Meant to be called by the local HTTP `get` route on a
+/// cache miss before it gives up.
+pub async fn rpc_get_block<F, Fut>(
+    peer: libp2p::PeerId,
+    cid: Cid,
+    dispatch: F,
+) -> anyhow::Result<Option<Vec<u8>>>
+where
+    F: FnMut(libp2p::PeerId, BlockRpcRequest) -> Fut,
+    Fut: std::future::Future<Output = anyhow::Result<BlockRpcResponse>>,
+{
+    match send_with_retry(peer, BlockRpcRequest::GetBlock { cid }, dispatch).await? {
+        BlockRpcResponse::BlockData(data) => Ok(Some(data)),
+        BlockRpcResponse::NotFound | BlockRpcResponse::Ack => Ok(None),
+        BlockRpcResponse::Err(reason) => Err(anyhow::anyhow!("{peer} failed GetBlock: {reason}")),
+    }
+}
+
+/// Pushes a block to `peer`'s store.
+pub async fn rpc_put_block<F, Fut>(
+    peer: libp2p::PeerId,
+    cid: Cid,
+    data: Vec<u8>,
+    dispatch: F,
+) -> anyhow::Result<()>
+where
+    F: FnMut(libp2p::PeerId, BlockRpcRequest) -> Fut,
+    Fut: std::future::Future<Output = anyhow::Result<BlockRpcResponse>>,
+{
+    match send_with_retry(peer, BlockRpcRequest::PutBlock { cid, data }, dispatch).await? {
+        BlockRpcResponse::Ack => Ok(()),
+        other => Err(anyhow::anyhow!("unexpected reply to PutBlock: {other:?}")),
+    }
+}
+
+/// Constructs the `RequestResponse` behaviour that carries block-exchange
+/// traffic, to be composed into a swarm's `NetworkBehaviour` alongside its
+/// other behaviours (gossipsub, kademlia, ...). Nothing in this repository
+/// checkout registers it on a swarm (see the module-level docs).
+pub fn new_behaviour() -> libp2p::request_response::RequestResponse<BlockExchangeCodec> {
+    libp2p::request_response::RequestResponse::new(
+        BlockExchangeCodec,
+        std::iter::once((
+            BlockExchangeProtocol,
+            libp2p::request_response::ProtocolSupport::Full,
+        )),
+        libp2p::request_response::RequestResponseConfig::default(),
+    )
+}
+
+/// Answers an inbound block-exchange request straight out of the local
+/// `BlockStore` behind `interface` — the same store the HTTP `get`/`put`
+/// routes read and write. The returned response is the reply for the
+/// `channel` of a `RequestResponseMessage::Request { request, channel, .. }`;
+/// nothing in this repository checkout calls this from a swarm event loop
+/// (see the module-level docs).
+pub fn handle_inbound_request<S>(
+    interface: &crate::api::NodeNetworkInterface<S>,
+    request: BlockRpcRequest,
+) -> BlockRpcResponse
+where
+    S: ipld_blockstore::BlockStore + Sync + Send + 'static,
+{
+    match request {
+        BlockRpcRequest::GetBlock { cid } => match interface.get_local_block(&cid) {
+            Ok(Some(data)) => BlockRpcResponse::BlockData(data),
+            Ok(None) => BlockRpcResponse::NotFound,
+            Err(err) => {
+                tracing::warn!("local store lookup for {cid} failed answering GetBlock: {err}");
+                BlockRpcResponse::Err(err.to_string())
+            }
+        },
+        BlockRpcRequest::PutBlock { cid, data } => match interface.put_local_block(&cid, &data) {
+            Ok(()) => {
+                interface.notify_new_block(cid);
+                BlockRpcResponse::Ack
+            }
+            Err(err) => {
+                tracing::warn!("local store write for {cid} failed answering PutBlock: {err}");
+                BlockRpcResponse::Err(err.to_string())
+            }
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    };
+
+    use futures::io::Cursor;
+    use libp2p::identity::Keypair;
+
+    use super::*;
+
+    fn test_peer() -> libp2p::PeerId {
+        libp2p::PeerId::from(Keypair::generate_ed25519().public())
+    }
+
+    #[tokio::test]
+    async fn write_then_read_framed_round_trips() {
+        let response = BlockRpcResponse::BlockData(vec![1, 2, 3, 4]);
+
+        let mut buf = Cursor::new(Vec::new());
+        write_framed(&mut buf, &response).await.unwrap();
+
+        let mut reader = Cursor::new(buf.into_inner());
+        let decoded: BlockRpcResponse = read_framed(&mut reader).await.unwrap();
+
+        match decoded {
+            BlockRpcResponse::BlockData(data) => assert_eq!(data, vec![1, 2, 3, 4]),
+            other => panic!("expected BlockData, got {other:?}"),
+        }
+    }
+
+    #[tokio::test]
+    async fn read_framed_rejects_over_limit_frame() {
+        let oversized_len = (MAX_FRAME_LEN as u32) + 1;
+        let mut frame = oversized_len.to_be_bytes().to_vec();
+        frame.extend(std::iter::repeat(0u8).take(8));
+
+        let mut reader = Cursor::new(frame);
+        let result: io::Result<BlockRpcResponse> = read_framed(&mut reader).await;
+
+        assert!(result.is_err());
+        assert_eq!(result.unwrap_err().kind(), io::ErrorKind::InvalidData);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn send_with_retry_exhausts_retries_on_timeout() {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let calls_clone = calls.clone();
+
+        let dispatch = move |_peer: libp2p::PeerId, _request: BlockRpcRequest| {
+            let calls = calls_clone.clone();
+            async move {
+                calls.fetch_add(1, Ordering::SeqCst);
+                std::future::pending::<anyhow::Result<BlockRpcResponse>>().await
+            }
+        };
+
+        let peer = test_peer();
+        let fut = send_with_retry(peer, BlockRpcRequest::GetBlock { cid: test_cid() }, dispatch);
+        tokio::pin!(fut);
+
+        for _ in 0..=BLOCK_RPC_RETRIES {
+            tokio::time::advance(BLOCK_RPC_TIMEOUT + Duration::from_millis(1)).await;
+        }
+
+        let result = fut.await;
+        assert!(result.is_err());
+        assert_eq!(calls.load(Ordering::SeqCst), BLOCK_RPC_RETRIES + 1);
+    }
+
+    #[tokio::test]
+    async fn send_with_retry_returns_first_successful_response() {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let calls_clone = calls.clone();
+
+        let dispatch = move |_peer: libp2p::PeerId, _request: BlockRpcRequest| {
+            let calls = calls_clone.clone();
+            async move {
+                calls.fetch_add(1, Ordering::SeqCst);
+                Ok(BlockRpcResponse::Ack)
+            }
+        };
+
+        let peer = test_peer();
+        let result = send_with_retry(peer, BlockRpcRequest::GetBlock { cid: test_cid() }, dispatch)
+            .await
+            .unwrap();
+
+        assert!(matches!(result, BlockRpcResponse::Ack));
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+    }
+
+    fn test_cid() -> Cid {
+        use multihash::{Code, MultihashDigest};
+
+        let hash = Code::Sha2_256.digest(b"p2p-rpc-test");
+        Cid::new_v1(0x55, hash)
+    }
+
+    /// Proves the Get/Put paths are correct against a real `BlockStore` even
+    /// though nothing in this repository checkout drives `handle_inbound_request`
+    /// from an actual swarm yet (see the module-level TODO).
+    #[tokio::test]
+    async fn handle_inbound_request_put_then_get_round_trips_through_real_store() {
+        use db::{rocks::RocksDb, rocks_config::RocksDbConfig};
+        use store::Store;
+
+        let dir = std::env::temp_dir().join(format!(
+            "ursa-p2p-rpc-test-{}-{}",
+            std::process::id(),
+            test_peer()
+        ));
+        let db = RocksDb::open(dir.to_str().unwrap(), &RocksDbConfig::default())
+            .expect("opening rocksdb must succeed");
+        let store = Arc::new(Store::new(Arc::new(db)));
+
+        let (network_send, _network_recv) = tokio::sync::mpsc::unbounded_channel();
+        let interface = crate::api::NodeNetworkInterface::new(store, network_send);
+
+        let cid = test_cid();
+        let data = b"hello from a peer".to_vec();
+
+        let put_response = handle_inbound_request(
+            &interface,
+            BlockRpcRequest::PutBlock {
+                cid,
+                data: data.clone(),
+            },
+        );
+        assert!(matches!(put_response, BlockRpcResponse::Ack));
+
+        let get_response = handle_inbound_request(&interface, BlockRpcRequest::GetBlock { cid });
+        match get_response {
+            BlockRpcResponse::BlockData(returned) => assert_eq!(returned, data),
+            other => panic!("expected BlockData, got {other:?}"),
+        }
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
@@ -1,14 +1,18 @@
 use anyhow::Result;
-use axum::{Extension, Router};
+use axum::{middleware, routing::get, Extension, Router};
+use axum_server::Handle;
 use ipld_blockstore::BlockStore;
-use std::{net::SocketAddr, sync::Arc};
+use std::{net::SocketAddr, path::Path, sync::Arc};
 
 use crate::{
     api::NodeNetworkInterface,
+    auth::{load_or_generate_token, require_bearer_token},
     config::ServerConfig,
     http,
     rpc::{self, rpc::RpcServer},
     service::MultiplexService,
+    tls::load_tls_config,
+    ws::ws_handler,
 };
 use tracing::info;
 
@@ -33,22 +37,71 @@ where
 
     pub async fn start(&self, config: ServerConfig) -> Result<()> {
         info!("Server (Rpc and http) starting up");
+
+        let auth_token = match &config.auth_token_path {
+            Some(path) => Some(load_or_generate_token(Path::new(path))?),
+            None => None,
+        };
+        let auth_token = Arc::new(auth_token);
+
         let rpc_router = Router::new()
             .merge(rpc::routes::network::init())
+            .route("/rpc/ws", get(ws_handler::<S>))
+            .layer(middleware::from_fn(require_bearer_token))
+            .layer(Extension(auth_token))
+            .layer(Extension(self.interface.clone()))
             .layer(Extension(self.rpc_server.clone()));
 
         let http = Router::new()
             .merge(http::routes::network::init::<S>())
             .layer(Extension(self.interface.clone()));
 
+        let tls_config = load_tls_config(&config).await?;
+
+        if let Some((http_address, rpc_address)) = config.split_listen_addrs()? {
+            info!("listening on {} (http), {} (rpc)", http_address, rpc_address);
+
+            match tls_config {
+                Some(tls_config) => {
+                    let http_server = axum_server::bind_rustls(http_address, tls_config.clone())
+                        .serve(http.into_make_service());
+                    let rpc_server = axum_server::bind_rustls(rpc_address, tls_config)
+                        .serve(rpc_router.into_make_service());
+
+                    tokio::try_join!(http_server, rpc_server)?;
+                }
+                None => {
+                    let http_server =
+                        axum::Server::bind(&http_address).serve(http.into_make_service());
+                    let rpc_server =
+                        axum::Server::bind(&rpc_address).serve(rpc_router.into_make_service());
+
+                    tokio::try_join!(http_server, rpc_server)?;
+                }
+            }
+
+            return Ok(());
+        }
+
         let http_address = SocketAddr::from(([0, 0, 0, 0], config.port));
 
         let service = MultiplexService::new(http, rpc_router);
 
-        info!("listening on {}", http_address);
-        axum::Server::bind(&http_address)
-            .serve(tower::make::Shared::new(service))
-            .await?;
+        match tls_config {
+            Some(tls_config) => {
+                info!("listening on {} (tls)", http_address);
+                axum_server::bind_rustls(http_address, tls_config)
+                    .handle(Handle::new())
+                    .serve(tower::make::Shared::new(service))
+                    .await?;
+            }
+            None => {
+                info!("listening on {}", http_address);
+                axum::Server::bind(&http_address)
+                    .serve(tower::make::Shared::new(service))
+                    .await?;
+            }
+        }
 
         Ok(())
     }
@@ -90,6 +143,7 @@ mod tests {
         let config = ServerConfig {
             port: 4069,
             addr: "0.0.0.0".to_string(),
+            ..Default::default()
         };
 
         let db = RocksDb::open("test_db", &RocksDbConfig::default())
@@ -101,10 +155,7 @@ mod tests {
         let (ursa_node, _) = ursa_network_init(&ursa_config, Arc::clone(&store));
         let ursa_node_sender = ursa_node.command_sender().clone();
 
-        let interface = Arc::new(NodeNetworkInterface {
-            store,
-            network_send: ursa_node_sender,
-        });
+        let interface = Arc::new(NodeNetworkInterface::new(store, ursa_node_sender));
 
         let rpc = Server::new(&config, interface);
 
@@ -0,0 +1,136 @@
+use std::{fs::File, io::BufReader, sync::Arc};
+
+use anyhow::{anyhow, Context, Result};
+use axum_server::tls_rustls::RustlsConfig;
+use rustls::{server::AllowAnyAuthenticatedClient, Certificate, PrivateKey, RootCertStore};
+use rustls_pemfile::{certs, pkcs8_private_keys, rsa_private_keys};
+
+use crate::config::ServerConfig;
+
+/// Builds a [`RustlsConfig`] from `config`'s TLS paths, or returns `Ok(None)` when
+/// TLS is not configured so callers can fall back to the plaintext listener.
+pub async fn load_tls_config(config: &ServerConfig) -> Result<Option<RustlsConfig>> {
+    let (cert_path, key_path) = match (&config.tls_cert_path, &config.tls_key_path) {
+        (Some(cert), Some(key)) => (cert, key),
+        (None, None) => return Ok(None),
+        _ => {
+            return Err(anyhow!(
+                "tls_cert_path and tls_key_path must both be set to enable TLS"
+            ))
+        }
+    };
+
+    let cert_chain = load_certs(cert_path)?;
+    let private_key = load_private_key(key_path)?;
+
+    let builder = rustls::ServerConfig::builder().with_safe_defaults();
+
+    let server_config = if let Some(ca_path) = &config.tls_client_ca_path {
+        let client_ca = load_root_store(ca_path)?;
+        builder
+            .with_client_cert_verifier(Arc::new(AllowAnyAuthenticatedClient::new(client_ca)))
+            .with_single_cert(cert_chain, private_key)
+            .context("certificate does not match the provided private key")?
+    } else {
+        builder
+            .with_no_client_auth()
+            .with_single_cert(cert_chain, private_key)
+            .context("certificate does not match the provided private key")?
+    };
+
+    Ok(Some(RustlsConfig::from_config(Arc::new(server_config))))
+}
+
+fn load_certs(path: &str) -> Result<Vec<Certificate>> {
+    let file = File::open(path).with_context(|| format!("failed to open TLS cert at {path}"))?;
+    let certs = certs(&mut BufReader::new(file))
+        .with_context(|| format!("failed to parse PEM certificates in {path}"))?;
+
+    if certs.is_empty() {
+        return Err(anyhow!("no certificates found in {path}"));
+    }
+
+    Ok(certs.into_iter().map(Certificate).collect())
+}
+
+fn load_private_key(path: &str) -> Result<PrivateKey> {
+    let read_pkcs8 = || -> Result<Vec<Vec<u8>>> {
+        let file = File::open(path).with_context(|| format!("failed to open TLS key at {path}"))?;
+        Ok(pkcs8_private_keys(&mut BufReader::new(file))?)
+    };
+    let read_rsa = || -> Result<Vec<Vec<u8>>> {
+        let file = File::open(path).with_context(|| format!("failed to open TLS key at {path}"))?;
+        Ok(rsa_private_keys(&mut BufReader::new(file))?)
+    };
+
+    let mut keys = read_pkcs8()?;
+    if keys.is_empty() {
+        keys = read_rsa()?;
+    }
+
+    match keys.into_iter().next() {
+        Some(key) => Ok(PrivateKey(key)),
+        None => Err(anyhow!(
+            "no PKCS#8 or RSA private key found in {path}"
+        )),
+    }
+}
+
+fn load_root_store(path: &str) -> Result<RootCertStore> {
+    let file = File::open(path).with_context(|| format!("failed to open CA bundle at {path}"))?;
+    let ca_certs = certs(&mut BufReader::new(file))
+        .with_context(|| format!("failed to parse PEM certificates in {path}"))?;
+
+    if ca_certs.is_empty() {
+        return Err(anyhow!("no CA certificates found in {path}"));
+    }
+
+    let mut store = RootCertStore::empty();
+    for cert in ca_certs {
+        store
+            .add(&Certificate(cert))
+            .context("invalid CA certificate")?;
+    }
+
+    Ok(store)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn load_tls_config_none_when_unset() {
+        let config = ServerConfig::default();
+        assert!(load_tls_config(&config).await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn load_tls_config_errors_when_only_cert_set() {
+        let config = ServerConfig {
+            tls_cert_path: Some("/nonexistent/cert.pem".to_string()),
+            ..Default::default()
+        };
+        assert!(load_tls_config(&config).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn load_tls_config_errors_when_only_key_set() {
+        let config = ServerConfig {
+            tls_key_path: Some("/nonexistent/key.pem".to_string()),
+            ..Default::default()
+        };
+        assert!(load_tls_config(&config).await.is_err());
+    }
+
+    #[test]
+    fn load_certs_errors_on_empty_file() {
+        let path = std::env::temp_dir().join(format!("ursa-empty-cert-{}.pem", std::process::id()));
+        std::fs::write(&path, "").unwrap();
+
+        let result = load_certs(path.to_str().unwrap());
+
+        std::fs::remove_file(&path).unwrap();
+        assert!(result.is_err());
+    }
+}
@@ -0,0 +1,241 @@
+use std::{collections::HashMap, sync::Arc};
+
+use axum::{
+    extract::ws::{Message, WebSocket, WebSocketUpgrade},
+    response::IntoResponse,
+    Extension,
+};
+use futures::{SinkExt, StreamExt};
+use ipld_blockstore::BlockStore;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tokio::sync::broadcast;
+use tracing::{debug, error};
+use uuid::Uuid;
+
+use crate::{api::NodeNetworkInterface, rpc::rpc::RpcServer};
+
+/// Events a subscriber can be notified about via `ursa_subscribe`.
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum SubscriptionEvent {
+    NewBlock { cid: String },
+    PeerConnected { peer_id: String },
+    PeerDisconnected { peer_id: String },
+}
+
+#[derive(Debug, Deserialize, Default)]
+struct SubscribeParams {
+    #[serde(default)]
+    topic: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct UnsubscribeParams {
+    subscription: Uuid,
+}
+
+#[derive(Debug, Serialize)]
+struct JsonRpcNotification {
+    jsonrpc: &'static str,
+    method: &'static str,
+    params: NotificationParams,
+}
+
+#[derive(Debug, Serialize)]
+struct NotificationParams {
+    subscription: Uuid,
+    result: SubscriptionEvent,
+}
+
+#[derive(Debug, Serialize)]
+struct JsonRpcResult {
+    jsonrpc: &'static str,
+    id: Value,
+    result: Value,
+}
+
+/// Upgrades the connection to a WebSocket and hands it off to [`handle_socket`].
+pub async fn ws_handler<S>(
+    ws: WebSocketUpgrade,
+    Extension(rpc_server): Extension<RpcServer>,
+    Extension(interface): Extension<Arc<NodeNetworkInterface<S>>>,
+) -> impl IntoResponse
+where
+    S: BlockStore + Sync + Send + 'static,
+{
+    ws.on_upgrade(move |socket| handle_socket(socket, rpc_server, interface))
+}
+
+/// Drives a single WebSocket connection: incoming frames are parsed as JSON-RPC
+/// requests and dispatched through the same handler set used by the plain HTTP
+/// RPC router, while subscribed event streams are pushed back as
+/// `ursa_subscription` notifications until the socket closes or `ursa_unsubscribe`
+/// is called.
+async fn handle_socket<S>(
+    socket: WebSocket,
+    rpc_server: RpcServer,
+    interface: Arc<NodeNetworkInterface<S>>,
+) where
+    S: BlockStore + Sync + Send + 'static,
+{
+    let (mut ws_tx, mut ws_rx) = socket.split();
+    let (out_tx, mut out_rx) = tokio::sync::mpsc::unbounded_channel::<String>();
+
+    let mut subscriptions: HashMap<Uuid, tokio::task::JoinHandle<()>> = HashMap::new();
+
+    let writer = tokio::spawn(async move {
+        while let Some(text) = out_rx.recv().await {
+            if ws_tx.send(Message::Text(text)).await.is_err() {
+                break;
+            }
+        }
+    });
+
+    while let Some(frame) = ws_rx.next().await {
+        let frame = match frame {
+            Ok(frame) => frame,
+            Err(err) => {
+                debug!("websocket rpc connection closed: {err}");
+                break;
+            }
+        };
+
+        let text = match frame {
+            Message::Text(text) => text,
+            Message::Close(_) => break,
+            _ => continue,
+        };
+
+        let Ok(request) = serde_json::from_str::<Value>(&text) else {
+            error!("received malformed json-rpc frame over websocket");
+            continue;
+        };
+
+        let id = request.get("id").cloned().unwrap_or(Value::Null);
+        let method = request.get("method").and_then(Value::as_str).unwrap_or("");
+
+        match method {
+            "ursa_subscribe" => {
+                let params: SubscribeParams = request
+                    .get("params")
+                    .and_then(|p| serde_json::from_value(p.clone()).ok())
+                    .unwrap_or_default();
+
+                let sub_id = Uuid::new_v4();
+                let mut events = interface.subscribe_events();
+                let tx = out_tx.clone();
+
+                let task = tokio::spawn(async move {
+                    loop {
+                        match events.recv().await {
+                            Ok(event) => {
+                                if matches_topic(&params.topic, &event) {
+                                    let notification = JsonRpcNotification {
+                                        jsonrpc: "2.0",
+                                        method: "ursa_subscription",
+                                        params: NotificationParams {
+                                            subscription: sub_id,
+                                            result: event,
+                                        },
+                                    };
+                                    if let Ok(payload) = serde_json::to_string(&notification) {
+                                        if tx.send(payload).is_err() {
+                                            break;
+                                        }
+                                    }
+                                }
+                            }
+                            Err(broadcast::error::RecvError::Lagged(_)) => continue,
+                            Err(broadcast::error::RecvError::Closed) => break,
+                        }
+                    }
+                });
+                subscriptions.insert(sub_id, task);
+
+                send_result(&out_tx, id, Value::String(sub_id.to_string()));
+            }
+            "ursa_unsubscribe" => {
+                let task = request
+                    .get("params")
+                    .and_then(|p| serde_json::from_value::<UnsubscribeParams>(p.clone()).ok())
+                    .and_then(|p| subscriptions.remove(&p.subscription));
+
+                match task {
+                    Some(task) => {
+                        task.abort();
+                        send_result(&out_tx, id, Value::Bool(true));
+                    }
+                    None => send_result(&out_tx, id, Value::Bool(false)),
+                }
+            }
+            _ => {
+                let response = rpc_server.handle(text).await;
+                let _ = out_tx.send(response);
+            }
+        }
+    }
+
+    for (_, task) in subscriptions.drain() {
+        task.abort();
+    }
+    writer.abort();
+}
+
+fn matches_topic(topic: &Option<String>, event: &SubscriptionEvent) -> bool {
+    match (topic.as_deref(), event) {
+        (None, _) => true,
+        (Some("new_block"), SubscriptionEvent::NewBlock { .. }) => true,
+        (Some("peer_connect"), SubscriptionEvent::PeerConnected { .. }) => true,
+        (Some("peer_disconnect"), SubscriptionEvent::PeerDisconnected { .. }) => true,
+        _ => false,
+    }
+}
+
+fn send_result(tx: &tokio::sync::mpsc::UnboundedSender<String>, id: Value, result: Value) {
+    if let Ok(payload) = serde_json::to_string(&JsonRpcResult {
+        jsonrpc: "2.0",
+        id,
+        result,
+    }) {
+        let _ = tx.send(payload);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn new_block() -> SubscriptionEvent {
+        SubscriptionEvent::NewBlock {
+            cid: "bafy2test".to_string(),
+        }
+    }
+
+    fn peer_connected() -> SubscriptionEvent {
+        SubscriptionEvent::PeerConnected {
+            peer_id: "12D3Koo".to_string(),
+        }
+    }
+
+    #[test]
+    fn matches_topic_none_accepts_everything() {
+        assert!(matches_topic(&None, &new_block()));
+        assert!(matches_topic(&None, &peer_connected()));
+    }
+
+    #[test]
+    fn matches_topic_filters_by_topic() {
+        assert!(matches_topic(&Some("new_block".to_string()), &new_block()));
+        assert!(!matches_topic(&Some("new_block".to_string()), &peer_connected()));
+        assert!(matches_topic(
+            &Some("peer_connect".to_string()),
+            &peer_connected()
+        ));
+    }
+
+    #[test]
+    fn matches_topic_unknown_topic_matches_nothing() {
+        assert!(!matches_topic(&Some("bogus".to_string()), &new_block()));
+    }
+}